@@ -1,20 +1,102 @@
-use std::{env, fs, path::PathBuf};
+use std::{collections::HashMap, env, fs, path::PathBuf};
 
-use alloy_json_abi::Function;
+use alloy::hex;
+use alloy_json_abi::{Event, Function};
+
+use crate::error::TxDecodeError;
+
+/// Returns the path to `~/.txdecode`, creating it if it doesn't exist.
+fn txdecode_dir() -> eyre::Result<PathBuf> {
+    let home = env::var("HOME").or_else(|_| env::var("USERPROFILE"))?;
+    let dir = PathBuf::from(home).join(".txdecode");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
 
 /// Returns the path to the cache directory, creating it if it doesn't exist.
 pub fn cache_dir() -> eyre::Result<PathBuf> {
-    let home = env::var("HOME").or_else(|_| env::var("USERPROFILE"))?;
-    let cache = PathBuf::from(home).join(".txdecode").join("cache");
+    let cache = txdecode_dir()?.join("cache");
     fs::create_dir_all(&cache)?;
     Ok(cache)
 }
 
+/// Returns the path to the offline selector -> signatures cache.
+fn signatures_cache_path() -> eyre::Result<PathBuf> {
+    Ok(txdecode_dir()?.join("signatures.json"))
+}
+
+/// Loads the full offline selector -> signatures cache from disk.
+fn load_signatures_cache() -> HashMap<String, Vec<String>> {
+    signatures_cache_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Looks up the cached signatures for `selector`, if any were persisted by a previous run.
+pub fn load_cached_signatures(selector: [u8; 4]) -> Option<Vec<String>> {
+    let key = format!("0x{}", hex::encode(selector));
+    load_signatures_cache().get(&key).cloned()
+}
+
+/// Persists the signatures looked up for `selector` to the offline cache so later runs (and
+/// CI, with `--offline`) can resolve it without network access.
+pub fn save_cached_signatures(selector: [u8; 4], signatures: &[String]) -> Result<(), TxDecodeError> {
+    let path = signatures_cache_path().map_err(|e| TxDecodeError::CacheIo(e.to_string()))?;
+    let mut all = load_signatures_cache();
+    let key = format!("0x{}", hex::encode(selector));
+    all.insert(key, signatures.to_vec());
+
+    let json = serde_json::to_string_pretty(&all).map_err(|e| TxDecodeError::CacheIo(e.to_string()))?;
+    fs::write(path, json).map_err(|e| TxDecodeError::CacheIo(e.to_string()))?;
+    Ok(())
+}
+
+/// Returns the path to the offline topic0 -> event signatures cache.
+fn event_signatures_cache_path() -> eyre::Result<PathBuf> {
+    Ok(txdecode_dir()?.join("event_signatures.json"))
+}
+
+/// Loads the full offline topic0 -> event signatures cache from disk.
+fn load_event_signatures_cache() -> HashMap<String, Vec<String>> {
+    event_signatures_cache_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Looks up the cached event signatures for `topic0`, if any were persisted by a previous run.
+pub fn load_cached_event_signatures(topic0: &str) -> Option<Vec<String>> {
+    load_event_signatures_cache().get(topic0).cloned()
+}
+
+/// Persists the event signatures looked up for `topic0` to the offline cache so later runs
+/// (and CI, with `--offline`) can resolve it without network access.
+pub fn save_cached_event_signatures(
+    topic0: &str,
+    signatures: &[String],
+) -> Result<(), TxDecodeError> {
+    let path = event_signatures_cache_path().map_err(|e| TxDecodeError::CacheIo(e.to_string()))?;
+    let mut all = load_event_signatures_cache();
+    all.insert(topic0.to_string(), signatures.to_vec());
+
+    let json = serde_json::to_string_pretty(&all).map_err(|e| TxDecodeError::CacheIo(e.to_string()))?;
+    fs::write(path, json).map_err(|e| TxDecodeError::CacheIo(e.to_string()))?;
+    Ok(())
+}
+
 /// Returns the cache file path for a given contract address.
 pub fn cache_path(address: &str) -> eyre::Result<PathBuf> {
     Ok(cache_dir()?.join(format!("{}.json", address.to_lowercase())))
 }
 
+/// Returns the cache file path for a given contract address's events.
+pub fn events_cache_path(address: &str) -> eyre::Result<PathBuf> {
+    Ok(cache_dir()?.join(format!("{}.events.json", address.to_lowercase())))
+}
+
 /// Loads the cached ABI for the given contract address, if it exists.
 pub fn load_cache_abi(address: &str) -> Option<Vec<Function>> {
     let path = cache_path(address).ok()?;
@@ -30,6 +112,52 @@ pub fn save_cached_abi(address: &str, abi: &[Function]) -> eyre::Result<()> {
     Ok(())
 }
 
+/// Loads the cached events for the given contract address, if they exist.
+pub fn load_cached_events(address: &str) -> Option<Vec<Event>> {
+    let path = events_cache_path(address).ok()?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Saves the given events to the cache for the specified contract address.
+pub fn save_cached_events(address: &str, events: &[Event]) -> eyre::Result<()> {
+    let path = events_cache_path(address)?;
+    let json = serde_json::to_string_pretty(events)?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Returns the cache file path for a given address's resolved ENS name.
+pub fn ens_cache_path(address: &str) -> eyre::Result<PathBuf> {
+    Ok(cache_dir()?.join(format!("{}.ens.json", address.to_lowercase())))
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EnsCacheEntry {
+    name: Option<String>,
+}
+
+/// Loads the cached ENS reverse-resolution result for the given address, if it exists.
+/// The outer `Option` is whether anything is cached; the inner `Option` is whether that
+/// cached result was a resolved name (`None` means "looked up, found no valid name").
+pub fn load_cached_ens_name(address: &str) -> Option<Option<String>> {
+    let path = ens_cache_path(address).ok()?;
+    let content = fs::read_to_string(path).ok()?;
+    let entry: EnsCacheEntry = serde_json::from_str(&content).ok()?;
+    Some(entry.name)
+}
+
+/// Caches the ENS reverse-resolution result (or lack thereof) for the given address.
+pub fn save_cached_ens_name(address: &str, name: Option<&str>) -> eyre::Result<()> {
+    let path = ens_cache_path(address)?;
+    let entry = EnsCacheEntry {
+        name: name.map(str::to_string),
+    };
+    let json = serde_json::to_string_pretty(&entry)?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -47,4 +175,39 @@ mod tests {
         let path = cache_path(address).unwrap();
         assert!(path.ends_with("0xabc123.json"));
     }
+
+    #[test]
+    fn test_events_cache_path_normalization() {
+        let address = "0xAbC123";
+        let path = events_cache_path(address).unwrap();
+        assert!(path.ends_with("0xabc123.events.json"));
+    }
+
+    #[test]
+    fn test_ens_cache_path_normalization() {
+        let address = "0xAbC123";
+        let path = ens_cache_path(address).unwrap();
+        assert!(path.ends_with("0xabc123.ens.json"));
+    }
+
+    #[test]
+    fn test_signature_cache_roundtrip() {
+        let selector = [0x12, 0x34, 0x56, 0x78];
+        let signatures = vec!["doesNotExist(uint256)".to_string()];
+
+        save_cached_signatures(selector, &signatures).unwrap();
+        assert_eq!(load_cached_signatures(selector), Some(signatures));
+    }
+
+    #[test]
+    fn test_event_signature_cache_roundtrip() {
+        let topic0 = "0xdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef";
+        let signatures = vec!["DoesNotExist(uint256)".to_string()];
+
+        save_cached_event_signatures(topic0, &signatures).unwrap();
+        assert_eq!(
+            load_cached_event_signatures(topic0),
+            Some(signatures)
+        );
+    }
 }
@@ -1,46 +1,197 @@
+use std::future::Future;
+use std::pin::Pin;
+
 use alloy::{
     dyn_abi::{DynSolValue, JsonAbiExt},
     hex,
     primitives::Bytes,
 };
-use alloy_json_abi::Function;
-use eyre::{bail, eyre};
+use alloy_json_abi::{Constructor, Error as AbiError, Function, JsonAbi};
 
+use crate::error::TxDecodeError;
 use crate::etherscan;
 use crate::signatures;
 
+/// Default recursion limit for [`decode_calldata_tree`], guarding against pathological or
+/// malicious nesting in aggregator/proxy calldata.
+pub const DEFAULT_MAX_NESTED_DEPTH: u8 = 4;
+
+/// Selector of the compiler-generated `Error(string)` revert, used for `require`/`revert`
+/// reason strings.
+pub const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of the compiler-generated `Panic(uint256)` revert, used for `assert` failures,
+/// arithmetic over/underflow, and other internal Solidity panics.
+pub const PANIC_UINT256_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decodes a standard Solidity revert payload (`Error(string)` or `Panic(uint256)`).
+///
+/// This only covers the two compiler-generated selectors; a custom `error Foo(...)` requires
+/// its own ABI to resolve and isn't handled here.
+pub fn decode_revert(data: &Bytes) -> Result<(AbiError, Vec<DynSolValue>), TxDecodeError> {
+    let sel = signatures::selector(data)?;
+
+    let error = match sel {
+        ERROR_STRING_SELECTOR => AbiError::parse("Error(string)").map_err(|e| {
+            TxDecodeError::RevertDecodeFailed(format!(
+                "failed to parse built-in Error(string): {}",
+                e
+            ))
+        })?,
+        PANIC_UINT256_SELECTOR => AbiError::parse("Panic(uint256)").map_err(|e| {
+            TxDecodeError::RevertDecodeFailed(format!(
+                "failed to parse built-in Panic(uint256): {}",
+                e
+            ))
+        })?,
+        _ => {
+            return Err(TxDecodeError::RevertDecodeFailed(format!(
+                "unrecognized revert selector 0x{}",
+                hex::encode(sel)
+            )))
+        }
+    };
+
+    let params = data.get(4..).ok_or_else(|| {
+        TxDecodeError::RevertDecodeFailed("revert data missing parameters".to_string())
+    })?;
+    let decoded = error
+        .abi_decode_input(params)
+        .map_err(|e| TxDecodeError::RevertDecodeFailed(format!("failed to decode revert: {}", e)))?;
+
+    Ok((error, decoded))
+}
+
+/// Result of resolving calldata against a full [`JsonAbi`] (see [`decode_with_abi`]): either a
+/// matched function call or a matched revert/error, each carrying the other ABI entries with
+/// the same selector (a rare but possible overload/4-byte collision) that were tried before
+/// the match and failed to decode. Entries after the match are not tried and not listed.
+pub enum AbiDispatch {
+    Function {
+        func: Function,
+        params: Vec<DynSolValue>,
+        alternatives: Vec<Function>,
+    },
+    Error {
+        error: AbiError,
+        params: Vec<DynSolValue>,
+        alternatives: Vec<AbiError>,
+    },
+}
+
+/// Resolves `calldata` against a full contract ABI by its leading 4-byte selector, trying
+/// functions first and then errors (for revert payloads). When several entries share the
+/// selector (an overload/4-byte collision), each is tried in turn and the first that decodes
+/// cleanly wins; the candidates tried before it that failed are reported back as
+/// `alternatives`. Candidates after the winner are neither tried nor reported.
+pub fn decode_with_abi(abi: &JsonAbi, calldata: &Bytes) -> Result<AbiDispatch, TxDecodeError> {
+    let sel = signatures::selector(calldata)?;
+
+    let candidates: Vec<&Function> = abi.functions().filter(|f| f.selector() == sel).collect();
+    if !candidates.is_empty() {
+        let mut tried = Vec::new();
+        for func in candidates {
+            match try_decode(func, calldata) {
+                Ok(params) => {
+                    return Ok(AbiDispatch::Function {
+                        func: func.clone(),
+                        params,
+                        alternatives: tried,
+                    });
+                }
+                Err(_) => tried.push(func.clone()),
+            }
+        }
+        return Err(TxDecodeError::AbiDispatchFailed(format!(
+            "found {} function(s) matching selector 0x{} but none decoded the calldata cleanly",
+            tried.len(),
+            hex::encode(sel)
+        )));
+    }
+
+    let error_candidates: Vec<&AbiError> = abi.errors().filter(|e| e.selector() == sel).collect();
+    if !error_candidates.is_empty() {
+        let params = calldata.get(4..).ok_or_else(|| {
+            TxDecodeError::AbiDispatchFailed("calldata missing parameters".to_string())
+        })?;
+
+        let mut tried = Vec::new();
+        for error in error_candidates {
+            match error.abi_decode_input(params) {
+                Ok(decoded) => {
+                    return Ok(AbiDispatch::Error {
+                        error: error.clone(),
+                        params: decoded,
+                        alternatives: tried,
+                    });
+                }
+                Err(_) => tried.push(error.clone()),
+            }
+        }
+        return Err(TxDecodeError::AbiDispatchFailed(format!(
+            "found {} error(s) matching selector 0x{} but none decoded the calldata cleanly",
+            tried.len(),
+            hex::encode(sel)
+        )));
+    }
+
+    Err(TxDecodeError::AbiDispatchFailed(format!(
+        "no function or error in the ABI matches selector 0x{}",
+        hex::encode(sel)
+    )))
+}
+
+/// Decodes a deployment transaction's constructor arguments. Unlike regular calldata, a
+/// deployment's `data` is unknown-length creation bytecode followed by the ABI-encoded
+/// constructor arguments with no selector to mark the split, so the caller must already have
+/// isolated the argument tail (e.g. by knowing the compiled bytecode's length).
+pub fn decode_constructor(
+    ctor: &Constructor,
+    args_tail: &Bytes,
+) -> Result<Vec<DynSolValue>, TxDecodeError> {
+    ctor.abi_decode_input(args_tail).map_err(|e| {
+        TxDecodeError::ConstructorDecodeFailed(format!(
+            "failed to decode constructor arguments: {}",
+            e
+        ))
+    })
+}
+
 /// Attempts to decode the given calldata using the provided Alloy Function.
 /// DynSolValue is a dynamic representation of Solidity values (not at compile time).
-fn try_decode(func: &Function, calldata: &Bytes) -> eyre::Result<Vec<DynSolValue>> {
+fn try_decode(func: &Function, calldata: &Bytes) -> Result<Vec<DynSolValue>, TxDecodeError> {
     // Skip the first 4 bytes (the function selector)
-    let params = calldata
-        .get(4..)
-        .ok_or_else(|| eyre!("calldata missing parameters"))?;
+    let params = calldata.get(4..).ok_or_else(|| {
+        TxDecodeError::CalldataDecodeFailed(
+            func.name.clone(),
+            "calldata missing parameters".to_string(),
+        )
+    })?;
 
     // Decode the parameters using the function's input ABI
     let decoded = func
         .abi_decode_input(params)
-        .map_err(|e| eyre!("failed to decode: {}", e))?;
+        .map_err(|e| TxDecodeError::CalldataDecodeFailed(func.name.clone(), e.to_string()))?;
 
     Ok(decoded)
 }
 
 /// Decodes the given calldata by looking up possible function signatures and trying to decode
-/// with each until one succeeds.
+/// with each until one succeeds. When `offline` is `true`, signature lookup is restricted to
+/// the local cache (see [`signatures::lookup_selector`]) and no HTTP requests are made.
 pub async fn decode_calldata(
     calldata: &Bytes,
     contract_address: Option<&str>,
     etherscan_key: Option<&str>,
     chain: Option<u64>,
+    offline: bool,
 ) -> eyre::Result<(Function, Vec<DynSolValue>)> {
     let sel = signatures::selector(calldata)?;
-    let signatures = signatures::lookup_selector(sel).await?;
+    let signatures = signatures::lookup_selector(sel, offline)
+        .await
+        .unwrap_or_default();
     let chain_id = chain.unwrap_or(1);
 
-    if signatures.is_empty() {
-        bail!("no signatures found for selector 0x{}", hex::encode(sel));
-    }
-
     // Priroritize common signatures (e.g., ERC-20 transfer)
     let mut prioritized: Vec<&String> = signatures
         .iter()
@@ -69,15 +220,161 @@ pub async fn decode_calldata(
 
     // Fallback to Etherscan ABI if contract address and API key are provided
     if let (Some(addr), Some(key)) = (contract_address, etherscan_key) {
-        let func = etherscan::fetch_etherscan_abi(chain_id, addr, sel, key).await?;
+        if offline {
+            return Err(TxDecodeError::AllSignaturesFailed.into());
+        }
+
+        let func = etherscan::fetch_etherscan_abi(addr, sel, key, Some(chain_id as u32)).await?;
         let decoded = try_decode(&func, calldata)?;
         return Ok((func, decoded));
     }
 
-    bail!(
-        "all {} signatures failed to decode calldata",
-        signatures.len()
+    Err(TxDecodeError::AllSignaturesFailed.into())
+}
+
+/// A decoded call together with any nested calls discovered within its `bytes`/`bytes[]`
+/// arguments (Multicall3, Gnosis Safe `execTransaction`, Uniswap's `multicall(bytes[])`, ...).
+#[derive(Debug)]
+pub struct DecodedCall {
+    pub func: Function,
+    pub params: Vec<DynSolValue>,
+    pub nested: Vec<DecodedCall>,
+}
+
+/// Decodes `calldata` like [`decode_calldata`], then walks the decoded parameters looking for
+/// `bytes`/`bytes[]` values that look like embedded calldata (a leading 4-byte selector) and
+/// recursively decodes those too, up to `max_depth` levels deep.
+///
+/// Byte blobs that don't decode against any known signature or the Etherscan ABI are left as
+/// opaque `bytes` (no nested entry is added for them).
+pub async fn decode_calldata_tree(
+    calldata: &Bytes,
+    contract_address: Option<&str>,
+    etherscan_key: Option<&str>,
+    chain: Option<u64>,
+    max_depth: u8,
+    offline: bool,
+) -> eyre::Result<DecodedCall> {
+    let (func, params) =
+        decode_calldata(calldata, contract_address, etherscan_key, chain, offline).await?;
+
+    let mut nested = Vec::new();
+    if max_depth > 0 {
+        for value in &params {
+            nested.extend(
+                find_nested_calls(
+                    value,
+                    contract_address,
+                    etherscan_key,
+                    chain,
+                    max_depth - 1,
+                    offline,
+                )
+                .await,
+            );
+        }
+    }
+
+    Ok(DecodedCall {
+        func,
+        params,
+        nested,
+    })
+}
+
+/// Recursively walks a decoded value looking for `bytes` blobs that might themselves be
+/// encoded calls, decoding any that are found. Boxed because `async fn`s can't recurse
+/// directly into themselves.
+fn find_nested_calls<'a>(
+    value: &'a DynSolValue,
+    contract_address: Option<&'a str>,
+    etherscan_key: Option<&'a str>,
+    chain: Option<u64>,
+    max_depth: u8,
+    offline: bool,
+) -> Pin<Box<dyn Future<Output = Vec<DecodedCall>> + Send + 'a>> {
+    Box::pin(async move {
+        match value {
+            DynSolValue::Bytes(bytes) => {
+                match try_decode_nested(
+                    bytes,
+                    contract_address,
+                    etherscan_key,
+                    chain,
+                    max_depth,
+                    offline,
+                )
+                .await
+                {
+                    Some(call) => vec![call],
+                    None => Vec::new(),
+                }
+            }
+            DynSolValue::Array(items) | DynSolValue::FixedArray(items) => {
+                let mut out = Vec::new();
+                for item in items {
+                    out.extend(
+                        find_nested_calls(
+                            item,
+                            contract_address,
+                            etherscan_key,
+                            chain,
+                            max_depth,
+                            offline,
+                        )
+                        .await,
+                    );
+                }
+                out
+            }
+            DynSolValue::Tuple(items) | DynSolValue::CustomStruct { tuple: items, .. } => {
+                let mut out = Vec::new();
+                for item in items {
+                    out.extend(
+                        find_nested_calls(
+                            item,
+                            contract_address,
+                            etherscan_key,
+                            chain,
+                            max_depth,
+                            offline,
+                        )
+                        .await,
+                    );
+                }
+                out
+            }
+            _ => Vec::new(),
+        }
+    })
+}
+
+/// Attempts to decode a byte blob as nested calldata. Returns `None` (rather than an error)
+/// when the blob is too short to carry a selector, or fails to decode against every known
+/// signature and the Etherscan ABI, since most `bytes` parameters aren't nested calls at all.
+async fn try_decode_nested(
+    bytes: &[u8],
+    contract_address: Option<&str>,
+    etherscan_key: Option<&str>,
+    chain: Option<u64>,
+    max_depth: u8,
+    offline: bool,
+) -> Option<DecodedCall> {
+    if bytes.len() < 4 {
+        return None;
+    }
+
+    let candidate = Bytes::from(bytes.to_vec());
+    decode_calldata_tree(
+        &candidate,
+        contract_address,
+        etherscan_key,
+        chain,
+        max_depth,
+        offline,
     )
+    .await
+    .ok()
 }
 
 #[cfg(test)]
@@ -103,8 +400,217 @@ mod tests {
             hex::decode("a9059cbb0000000000000000000000000742d35cc6634c0532925a3b844bc9e7595f0beb00000000000000000000000000000000000000000000000000000000000f4240").unwrap()
         );
 
-        let (func, params) = decode_calldata(&calldata, None, None, None).await.unwrap();
+        let (func, params) = decode_calldata(&calldata, None, None, None, false)
+            .await
+            .unwrap();
         assert_eq!(func.name, "transfer");
         assert_eq!(params.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_decode_calldata_tree_no_nested_calls() {
+        let calldata = Bytes::from(
+            hex::decode("a9059cbb0000000000000000000000000742d35cc6634c0532925a3b844bc9e7595f0beb00000000000000000000000000000000000000000000000000000000000f4240").unwrap()
+        );
+
+        let call = decode_calldata_tree(
+            &calldata,
+            None,
+            None,
+            None,
+            DEFAULT_MAX_NESTED_DEPTH,
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(call.func.name, "transfer");
+        assert!(call.nested.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_try_decode_nested_skips_short_blobs() {
+        let result = try_decode_nested(
+            &[0xde, 0xad],
+            None,
+            None,
+            None,
+            DEFAULT_MAX_NESTED_DEPTH,
+            false,
+        )
+        .await;
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_decode_calldata_tree_decodes_nested_call() {
+        // A Multicall3-style `execute(bytes)` wrapping an ERC-20 `transfer(address,uint256)`
+        // call: the outer decode should surface the inner call under `nested`.
+        let inner_func = signatures::parse_signature("transfer(address,uint256)").unwrap();
+        let inner_calldata = Bytes::from(
+            hex::decode("a9059cbb0000000000000000000000000742d35cc6634c0532925a3b844bc9e7595f0beb00000000000000000000000000000000000000000000000000000000000f4240").unwrap()
+        );
+
+        let outer_func = signatures::parse_signature("execute(bytes)").unwrap();
+        let addr = "0x000000000000000000000000000000000000f2";
+        crate::cache::save_cached_abi(addr, &[outer_func.clone(), inner_func]).unwrap();
+
+        let outer_calldata = outer_func
+            .abi_encode_input(&[DynSolValue::Bytes(inner_calldata.to_vec())])
+            .unwrap();
+
+        let call = decode_calldata_tree(
+            &Bytes::from(outer_calldata),
+            Some(addr),
+            Some("fake-api-key"),
+            None,
+            DEFAULT_MAX_NESTED_DEPTH,
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(call.func.name, "execute");
+        assert_eq!(call.nested.len(), 1);
+        assert_eq!(call.nested[0].func.name, "transfer");
+    }
+
+    #[tokio::test]
+    async fn test_decode_calldata_falls_back_to_etherscan_when_4byte_has_no_match() {
+        // A signature obscure enough that 4byte.directory won't have it (or the lookup is
+        // simply unavailable in this environment); either way `decode_calldata` must still
+        // reach the Etherscan fallback rather than bailing out early.
+        let func = signatures::parse_signature("testFooBarUncommon987(uint256)").unwrap();
+        let sel = func.selector();
+        let addr = "0x000000000000000000000000000000000000f1";
+        crate::cache::save_cached_abi(addr, std::slice::from_ref(&func)).unwrap();
+
+        let mut calldata = sel.to_vec();
+        calldata.extend_from_slice(&[0u8; 32]);
+        let calldata = Bytes::from(calldata);
+
+        let (decoded_func, params) =
+            decode_calldata(&calldata, Some(addr), Some("fake-api-key"), None, false)
+                .await
+                .unwrap();
+        assert_eq!(decoded_func.name, "testFooBarUncommon987");
+        assert_eq!(params.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_decode_calldata_offline_without_cache_fails() {
+        // An obscure selector that won't be in the offline cache, so offline mode must not
+        // fall back to a network request.
+        let calldata = Bytes::from(vec![0xff, 0xff, 0xff, 0xfd]);
+        let result = decode_calldata(&calldata, None, None, None, true).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_decode_revert_error_string() {
+        // Error(string) for revert("fail")
+        let data = Bytes::from(
+            hex::decode(
+                "08c379a0\
+                 0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000004\
+                 6661696c00000000000000000000000000000000000000000000000000000000",
+            )
+            .unwrap(),
+        );
+
+        let (error, params) = decode_revert(&data).unwrap();
+        assert_eq!(error.name, "Error");
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn test_decode_revert_panic() {
+        // Panic(uint256) with code 0x11 (arithmetic overflow)
+        let data = Bytes::from(
+            hex::decode(
+                "4e487b71\
+                 0000000000000000000000000000000000000000000000000000000000000011",
+            )
+            .unwrap(),
+        );
+
+        let (error, params) = decode_revert(&data).unwrap();
+        assert_eq!(error.name, "Panic");
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn test_decode_revert_unrecognized_selector() {
+        let data = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+        assert!(decode_revert(&data).is_err());
+    }
+
+    #[test]
+    fn test_decode_with_abi_matches_function() {
+        let abi: JsonAbi = serde_json::from_str(
+            r#"[{"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"}]"#,
+        )
+        .unwrap();
+
+        let calldata = Bytes::from(
+            hex::decode("a9059cbb0000000000000000000000000742d35cc6634c0532925a3b844bc9e7595f0beb00000000000000000000000000000000000000000000000000000000000f4240").unwrap()
+        );
+
+        match decode_with_abi(&abi, &calldata).unwrap() {
+            AbiDispatch::Function { func, params, .. } => {
+                assert_eq!(func.name, "transfer");
+                assert_eq!(params.len(), 2);
+            }
+            AbiDispatch::Error { .. } => panic!("expected a function match"),
+        }
+    }
+
+    #[test]
+    fn test_decode_with_abi_matches_error() {
+        let abi: JsonAbi = serde_json::from_str(
+            r#"[{"type":"error","name":"Error","inputs":[{"name":"message","type":"string"}]}]"#,
+        )
+        .unwrap();
+
+        let data = Bytes::from(
+            hex::decode(
+                "08c379a0\
+                 0000000000000000000000000000000000000000000000000000000000000020\
+                 0000000000000000000000000000000000000000000000000000000000000004\
+                 6661696c00000000000000000000000000000000000000000000000000000000",
+            )
+            .unwrap(),
+        );
+
+        match decode_with_abi(&abi, &data).unwrap() {
+            AbiDispatch::Error { error, params, .. } => {
+                assert_eq!(error.name, "Error");
+                assert_eq!(params.len(), 1);
+            }
+            AbiDispatch::Function { .. } => panic!("expected an error match"),
+        }
+    }
+
+    #[test]
+    fn test_decode_with_abi_no_match() {
+        let abi: JsonAbi = serde_json::from_str("[]").unwrap();
+        let data = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+        assert!(decode_with_abi(&abi, &data).is_err());
+    }
+
+    #[test]
+    fn test_decode_constructor() {
+        let abi: JsonAbi = serde_json::from_str(
+            r#"[{"type":"constructor","inputs":[{"name":"owner","type":"address"},{"name":"supply","type":"uint256"}],"stateMutability":"nonpayable"}]"#,
+        )
+        .unwrap();
+        let ctor = abi.constructor.as_ref().unwrap();
+
+        let args_tail = Bytes::from(
+            hex::decode("0000000000000000000000000742d35cc6634c0532925a3b844bc9e7595f0beb00000000000000000000000000000000000000000000000000000000000f4240").unwrap()
+        );
+
+        let params = decode_constructor(ctor, &args_tail).unwrap();
+        assert_eq!(params.len(), 2);
+    }
 }
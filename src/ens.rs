@@ -0,0 +1,151 @@
+use alloy::{
+    dyn_abi::{DynSolValue, FunctionExt, JsonAbiExt},
+    hex,
+    primitives::{keccak256, Address, Bytes, B256},
+};
+
+use crate::cache;
+use crate::error::TxDecodeError;
+use crate::rpc;
+use crate::signatures;
+
+/// Mainnet ENS registry with fallback (same address on every chain ENS has deployed to).
+const ENS_REGISTRY: &str = "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e";
+
+/// Computes the ENS namehash of a dotted name, e.g. `"vitalik.eth"` or
+/// `"<addr-without-0x>.addr.reverse"`.
+fn namehash(name: &str) -> B256 {
+    let mut node = B256::ZERO;
+    if name.is_empty() {
+        return node;
+    }
+
+    for label in name.rsplit('.') {
+        let label_hash = keccak256(label.as_bytes());
+        node = keccak256([node.as_slice(), label_hash.as_slice()].concat());
+    }
+
+    node
+}
+
+/// Calls a read-only function by signature through `eth_call` and returns its decoded outputs.
+async fn call_view(
+    rpc_url: &str,
+    contract: &str,
+    signature: &str,
+    args: &[DynSolValue],
+) -> Result<Vec<DynSolValue>, TxDecodeError> {
+    let func = signatures::parse_signature(signature)
+        .map_err(|e| TxDecodeError::EnsResolutionFailed(e.to_string()))?;
+    let calldata = func.abi_encode_input(args).map_err(|e| {
+        TxDecodeError::EnsResolutionFailed(format!("failed to encode {}: {}", signature, e))
+    })?;
+
+    let result = rpc::eth_call(rpc_url, contract, &Bytes::from(calldata)).await?;
+
+    func.abi_decode_output(&result).map_err(|e| {
+        TxDecodeError::EnsResolutionFailed(format!("failed to decode {} result: {}", signature, e))
+    })
+}
+
+/// Performs ENS reverse resolution for `address`: looks up `resolver(node)` on the ENS
+/// registry, then `name(node)` on that resolver, then forward-resolves the returned name's
+/// `addr(node)` and checks it matches `address` before trusting it (reverse records are set
+/// by whoever controls the reverse node, not necessarily by the forward name's owner).
+///
+/// Results (including "no name set") are cached per-address in `~/.txdecode`.
+pub async fn reverse_resolve(
+    rpc_url: &str,
+    address: &Address,
+) -> Result<Option<String>, TxDecodeError> {
+    let key = address.to_string().to_lowercase();
+    if let Some(cached) = cache::load_cached_ens_name(&key) {
+        return Ok(cached);
+    }
+
+    let name = resolve_uncached(rpc_url, address).await?;
+    cache::save_cached_ens_name(&key, name.as_deref())
+        .map_err(|e| TxDecodeError::CacheIo(e.to_string()))?;
+    Ok(name)
+}
+
+async fn resolve_uncached(
+    rpc_url: &str,
+    address: &Address,
+) -> Result<Option<String>, TxDecodeError> {
+    let reverse_name = format!("{}.addr.reverse", hex::encode(address));
+    let node = namehash(&reverse_name);
+
+    let Some(resolver) = resolver_for(rpc_url, node).await? else {
+        return Ok(None);
+    };
+
+    let outputs = call_view(
+        rpc_url,
+        &resolver.to_string(),
+        "name(bytes32)",
+        &[DynSolValue::FixedBytes(node, 32)],
+    )
+    .await?;
+
+    let Some(DynSolValue::String(name)) = outputs.into_iter().next() else {
+        return Ok(None);
+    };
+    if name.is_empty() {
+        return Ok(None);
+    }
+
+    // Forward-resolution sanity check: the name must resolve back to `address`.
+    let forward_node = namehash(&name);
+    let Some(forward_resolver) = resolver_for(rpc_url, forward_node).await? else {
+        return Ok(None);
+    };
+
+    let outputs = call_view(
+        rpc_url,
+        &forward_resolver.to_string(),
+        "addr(bytes32)",
+        &[DynSolValue::FixedBytes(forward_node, 32)],
+    )
+    .await?;
+
+    match outputs.into_iter().next() {
+        Some(DynSolValue::Address(resolved)) if resolved == *address => Ok(Some(name)),
+        _ => Ok(None),
+    }
+}
+
+/// Looks up `resolver(node)` on the ENS registry, returning `None` if no resolver is set.
+async fn resolver_for(rpc_url: &str, node: B256) -> Result<Option<Address>, TxDecodeError> {
+    let outputs = call_view(
+        rpc_url,
+        ENS_REGISTRY,
+        "resolver(bytes32)",
+        &[DynSolValue::FixedBytes(node, 32)],
+    )
+    .await?;
+
+    match outputs.into_iter().next() {
+        Some(DynSolValue::Address(resolver)) if !resolver.is_zero() => Ok(Some(resolver)),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namehash_eth_tld() {
+        // Well-known reference value for the "eth" TLD.
+        let expected: B256 = "0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+            .parse()
+            .unwrap();
+        assert_eq!(namehash("eth"), expected);
+    }
+
+    #[test]
+    fn test_namehash_empty_name_is_zero() {
+        assert_eq!(namehash(""), B256::ZERO);
+    }
+}
@@ -0,0 +1,42 @@
+use thiserror::Error;
+
+/// Typed failure modes for calldata/event decoding, so library consumers can match on what
+/// went wrong instead of string-matching an opaque error.
+#[derive(Debug, Error)]
+pub enum TxDecodeError {
+    #[error("data too short to extract a 4-byte selector")]
+    SelectorTooShort,
+
+    #[error("signature lookup failed: {0}")]
+    SignatureLookupFailed(String),
+
+    #[error("failed to fetch ABI from Etherscan: {0}")]
+    EtherscanFailed(String),
+
+    #[error("all candidate signatures failed to decode the calldata")]
+    AllSignaturesFailed,
+
+    #[error("cache I/O error: {0}")]
+    CacheIo(String),
+
+    #[error("failed to decode revert data: {0}")]
+    RevertDecodeFailed(String),
+
+    #[error("ABI dispatch failed: {0}")]
+    AbiDispatchFailed(String),
+
+    #[error("failed to decode constructor arguments: {0}")]
+    ConstructorDecodeFailed(String),
+
+    #[error("failed to decode calldata against {0}: {1}")]
+    CalldataDecodeFailed(String, String),
+
+    #[error("RPC request failed: {0}")]
+    RpcFailed(String),
+
+    #[error("ENS resolution failed: {0}")]
+    EnsResolutionFailed(String),
+
+    #[error("log decode failed: {0}")]
+    LogDecodeFailed(String),
+}
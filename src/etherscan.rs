@@ -1,12 +1,13 @@
 use std::time::Duration;
 
 use alloy::hex;
-use alloy_json_abi::{Function, JsonAbi};
-use eyre::{bail, eyre};
+use alloy::primitives::B256;
+use alloy_json_abi::{Event, Function, JsonAbi};
 use reqwest::Client;
 use serde::Deserialize;
 
 use crate::cache;
+use crate::error::TxDecodeError;
 
 #[derive(Debug, Deserialize)]
 struct EtherscanResponse {
@@ -14,55 +15,103 @@ struct EtherscanResponse {
     result: String,
 }
 
-/// Fetches the ABI from Etherscan for the given contract address and looks for a function
-/// matching the provided selector.
-pub async fn fetch_etherscan_abi(
+/// Fetches the full ABI JSON from Etherscan for the given contract address.
+async fn fetch_full_abi(
     contract_address: &str,
-    selector: [u8; 4],
     api_key: &str,
     chain_id: Option<u32>,
-) -> eyre::Result<Function> {
-    // Check cache first
-    if let Some(cached_abi) = cache::load_cache_abi(contract_address) {
-        if let Some(func) = cached_abi.iter().find(|f| f.selector() == selector) {
-            return Ok(func.clone());
-        }
-    }
-
-    // Fetch from Etherscan
+) -> Result<JsonAbi, TxDecodeError> {
     let chain = chain_id.unwrap_or(1);
     let url = format!(
         "https://api.etherscan.io/v2/api?module=contract&action=getabi&address={}&apikey={}&chainid={}",
         contract_address, api_key, chain
     );
 
-    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| TxDecodeError::EtherscanFailed(e.to_string()))?;
 
-    let response: EtherscanResponse = client.get(&url).send().await?.json().await?;
+    let response: EtherscanResponse = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| TxDecodeError::EtherscanFailed(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| TxDecodeError::EtherscanFailed(e.to_string()))?;
 
     if response.status != "1" {
-        bail!("failed to fetch ABI from Etherscan: {}", response.result);
+        return Err(TxDecodeError::EtherscanFailed(response.result));
     }
 
-    let full_abi: JsonAbi = serde_json::from_str(&response.result)
-        .map_err(|e| eyre!("failed to parse ABI JSON: {}", e))?;
+    serde_json::from_str(&response.result)
+        .map_err(|e| TxDecodeError::EtherscanFailed(format!("failed to parse ABI JSON: {}", e)))
+}
+
+/// Fetches the ABI from Etherscan for the given contract address and looks for a function
+/// matching the provided selector.
+pub async fn fetch_etherscan_abi(
+    contract_address: &str,
+    selector: [u8; 4],
+    api_key: &str,
+    chain_id: Option<u32>,
+) -> Result<Function, TxDecodeError> {
+    // Check cache first
+    if let Some(cached_abi) = cache::load_cache_abi(contract_address) {
+        if let Some(func) = cached_abi.iter().find(|f| f.selector() == selector) {
+            return Ok(func.clone());
+        }
+    }
 
+    let full_abi = fetch_full_abi(contract_address, api_key, chain_id).await?;
     let functions: Vec<Function> = full_abi.functions().cloned().collect();
 
     // Cache the ABI for future use
-    cache::save_cached_abi(contract_address, &functions)?;
+    cache::save_cached_abi(contract_address, &functions)
+        .map_err(|e| TxDecodeError::CacheIo(e.to_string()))?;
 
     functions
         .into_iter()
         .find(|f| f.selector() == selector)
         .ok_or_else(|| {
-            eyre!(
+            TxDecodeError::EtherscanFailed(format!(
                 "function with selector 0x{} not found in ABI",
                 hex::encode(selector)
-            )
+            ))
         })
 }
 
+/// Fetches the ABI from Etherscan for the given contract address and looks for an event
+/// matching the provided topic0 hash.
+pub async fn fetch_etherscan_event(
+    contract_address: &str,
+    topic0: B256,
+    api_key: &str,
+    chain_id: Option<u32>,
+) -> Result<Event, TxDecodeError> {
+    // Check cache first
+    if let Some(cached_events) = cache::load_cached_events(contract_address) {
+        if let Some(event) = cached_events.iter().find(|e| e.selector() == topic0) {
+            return Ok(event.clone());
+        }
+    }
+
+    let full_abi = fetch_full_abi(contract_address, api_key, chain_id).await?;
+    let events: Vec<Event> = full_abi.events().cloned().collect();
+
+    // Cache the events for future use
+    cache::save_cached_events(contract_address, &events)
+        .map_err(|e| TxDecodeError::CacheIo(e.to_string()))?;
+
+    events.into_iter().find(|e| e.selector() == topic0).ok_or_else(|| {
+        TxDecodeError::EtherscanFailed(format!(
+            "event with topic0 0x{} not found in ABI",
+            hex::encode(topic0)
+        ))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::env;
@@ -84,4 +133,21 @@ mod tests {
         assert_eq!(func.name, "transfer");
         assert_eq!(func.inputs.len(), 2);
     }
+
+    #[tokio::test]
+    #[ignore] // Requires a valid Etherscan API key
+    async fn test_fetch_etherscan_event() {
+        let api_key = env::var("ETHERSCAN_API_KEY").unwrap();
+        // USDT contract
+        let addr = "0xdac17f958d2ee523a2206206994597c13d831ec7";
+        // Transfer(address,address,uint256) topic0
+        let topic0: B256 = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+            .parse()
+            .unwrap();
+
+        let event = fetch_etherscan_event(addr, topic0, &api_key, None)
+            .await
+            .unwrap();
+        assert_eq!(event.name, "Transfer");
+    }
 }
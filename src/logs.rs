@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use alloy::{
+    dyn_abi::{DynSolValue, EventExt},
+    hex,
+};
+use alloy_json_abi::Event;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::cache;
+use crate::error::TxDecodeError;
+use crate::etherscan;
+use crate::rpc::{self, Log};
+
+/// Event names prioritized the same way [`crate::signatures::WELL_KNOWN_FUNC_NAME`]
+/// prioritizes functions.
+pub const WELL_KNOWN_EVENT_NAME: [&str; 2] = ["Transfer", "Approval"];
+
+#[derive(Debug, Deserialize)]
+struct FourByteEventResponse {
+    results: Vec<FourByteEventSignature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FourByteEventSignature {
+    text_signature: String,
+}
+
+/// A decoded log: the matched event plus its indexed and non-indexed argument values.
+pub struct DecodedLog {
+    pub event: Event,
+    pub indexed: Vec<DynSolValue>,
+    pub body: Vec<DynSolValue>,
+}
+
+/// Fetches the transaction receipt for `tx_hash` and returns its logs.
+pub async fn fetch_logs(rpc_url: &str, tx_hash: &str) -> Result<Vec<Log>, TxDecodeError> {
+    let receipt = rpc::get_transaction_receipt(rpc_url, tx_hash)
+        .await?
+        .ok_or_else(|| {
+            TxDecodeError::LogDecodeFailed(
+                "transaction receipt not found (unknown hash or still pending)".to_string(),
+            )
+        })?;
+
+    Ok(receipt.logs)
+}
+
+/// Looks up the given event topic0's signatures, consulting the offline cache at
+/// `~/.txdecode/event_signatures.json` before hitting the 4byte.directory event-signatures API.
+/// When `offline` is `true`, only the cache is consulted: a miss returns
+/// [`TxDecodeError::SignatureLookupFailed`] rather than attempting any HTTP request.
+async fn lookup_event_selector(
+    topic0: &[u8; 32],
+    offline: bool,
+) -> Result<Vec<String>, TxDecodeError> {
+    let hex_sig = format!("0x{}", hex::encode(topic0));
+
+    if let Some(cached) = cache::load_cached_event_signatures(&hex_sig) {
+        return Ok(cached);
+    }
+
+    if offline {
+        return Err(TxDecodeError::SignatureLookupFailed(format!(
+            "{} not in offline cache",
+            hex_sig
+        )));
+    }
+
+    let url = format!(
+        "https://www.4byte.directory/api/v1/event-signatures/?hex_signature={}",
+        hex_sig
+    );
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| TxDecodeError::SignatureLookupFailed(e.to_string()))?;
+
+    let response: FourByteEventResponse = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| TxDecodeError::SignatureLookupFailed(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| TxDecodeError::SignatureLookupFailed(e.to_string()))?;
+
+    let signatures: Vec<String> = response
+        .results
+        .into_iter()
+        .map(|r| r.text_signature)
+        .collect();
+
+    // Best-effort: a cache write failure shouldn't fail the lookup itself.
+    let _ = cache::save_cached_event_signatures(&hex_sig, &signatures);
+
+    Ok(signatures)
+}
+
+/// Decodes a single log, resolving the matching event the same way [`crate::decode`] resolves
+/// functions: prioritize well-known events from 4byte, then fall back to the Etherscan ABI
+/// cached for the log's emitting contract. When `offline` is `true`, event-signature lookup is
+/// restricted to the local cache and no HTTP requests are made (see [`lookup_event_selector`]).
+pub async fn decode_log(
+    log: &Log,
+    etherscan_key: Option<&str>,
+    chain: Option<u64>,
+    offline: bool,
+) -> Result<DecodedLog, TxDecodeError> {
+    let topic0 = *log.topics.first().ok_or_else(|| {
+        TxDecodeError::LogDecodeFailed("log has no topics, cannot resolve its event".to_string())
+    })?;
+
+    let signatures = lookup_event_selector(&topic0.0, offline)
+        .await
+        .unwrap_or_default();
+
+    let mut prioritized: Vec<&String> = signatures
+        .iter()
+        .filter(|s| WELL_KNOWN_EVENT_NAME.iter().any(|wk| s.starts_with(wk)))
+        .collect();
+    prioritized.extend(
+        signatures
+            .iter()
+            .filter(|s| !WELL_KNOWN_EVENT_NAME.iter().any(|wk| s.starts_with(wk))),
+    );
+
+    for sig in prioritized {
+        if let Ok(event) = Event::parse(sig) {
+            if let Ok(decoded) = try_decode_log(&event, log) {
+                return Ok(decoded);
+            }
+        }
+    }
+
+    // Fall back to the cached/fetched Etherscan ABI's events for the emitting contract
+    if let Some(cached) = cache::load_cached_events(&log.address) {
+        if let Some(event) = cached.iter().find(|e| e.selector() == topic0) {
+            if let Ok(decoded) = try_decode_log(event, log) {
+                return Ok(decoded);
+            }
+        }
+    }
+
+    if let Some(key) = etherscan_key {
+        if offline {
+            return Err(TxDecodeError::LogDecodeFailed(format!(
+                "no event signature found for topic0 0x{} in the offline cache",
+                hex::encode(topic0)
+            )));
+        }
+
+        let chain_id = chain.unwrap_or(1);
+        let event =
+            etherscan::fetch_etherscan_event(&log.address, topic0, key, Some(chain_id as u32))
+                .await?;
+        return try_decode_log(&event, log);
+    }
+
+    Err(TxDecodeError::LogDecodeFailed(format!(
+        "no event signature found for topic0 0x{}",
+        hex::encode(topic0)
+    )))
+}
+
+fn try_decode_log(event: &Event, log: &Log) -> Result<DecodedLog, TxDecodeError> {
+    let decoded = event
+        .decode_log_parts(log.topics.iter().copied(), &log.data)
+        .map_err(|e| TxDecodeError::LogDecodeFailed(format!("failed to decode log: {}", e)))?;
+
+    Ok(DecodedLog {
+        event: event.clone(),
+        indexed: decoded.indexed,
+        body: decoded.body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_decode_log_transfer() {
+        let event =
+            Event::parse("Transfer(address indexed,address indexed,uint256)").unwrap();
+
+        let from: alloy::primitives::B256 =
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+                .parse()
+                .unwrap();
+        let to: alloy::primitives::B256 =
+            "0x0000000000000000000000000000000000000000000000000000000000000002"
+                .parse()
+                .unwrap();
+
+        let log = Log {
+            address: "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string(),
+            topics: vec![event.selector(), from, to],
+            data: alloy::primitives::Bytes::from(
+                hex::decode("00000000000000000000000000000000000000000000000000000000000f4240")
+                    .unwrap(),
+            ),
+        };
+
+        let decoded = try_decode_log(&event, &log).unwrap();
+        assert_eq!(decoded.indexed.len(), 2);
+        assert_eq!(decoded.body.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_event_selector_offline_miss() {
+        // An obscure topic0 unlikely to already be cached from another test run.
+        let topic0 = [0xffu8; 32];
+        let result = lookup_event_selector(&topic0, true).await;
+        assert!(matches!(
+            result,
+            Err(TxDecodeError::SignatureLookupFailed(_))
+        ));
+    }
+}
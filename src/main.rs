@@ -1,43 +1,18 @@
-use std::{env, fs, path::PathBuf, time::Duration};
-
-use alloy::{
-    dyn_abi::{DynSolValue, JsonAbiExt},
-    hex,
-    primitives::Bytes,
-};
-use alloy_json_abi::Function;
+mod cache;
+mod decode;
+mod display;
+mod ens;
+mod error;
+mod etherscan;
+mod logs;
+mod rpc;
+mod signatures;
+mod trace;
+
+use alloy::hex;
+use alloy::primitives::Bytes;
 use clap::Parser;
-use comfy_table::{Attribute, Cell, Color, Table};
-use eyre::{bail, eyre};
-use reqwest::Client;
-use serde::Deserialize;
-
-// Constants
-const WELL_KNOWN_FUNC_NAME: [&str; 6] = [
-    "transfer",
-    "approve",
-    "transferFrom",
-    "mint",
-    "burn",
-    "swap",
-];
-
-// #[derive(Deserialize)] lets serde_json auto-parse the API response
-#[derive(Debug, Deserialize)]
-struct FourByteResponse {
-    results: Vec<FourByteSignature>,
-}
-
-#[derive(Debug, Deserialize)]
-struct FourByteSignature {
-    text_signature: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct EtherscanResponse {
-    status: String,
-    result: String,
-}
+use display::EnsNames;
 
 #[derive(Parser, Debug)]
 #[command(name = "txdecode", about = "Decode Ethereum transaction calldata", long_about = None)]
@@ -57,282 +32,240 @@ struct Args {
     /// Etherscan API key for ABI fetching
     #[arg(long, env = "ETHERSCAN_API_KEY")]
     etherscan_key: Option<String>,
-}
-
-/// Returns the path to the cache directory, creating it if it doesn't exist.
-fn cache_dir() -> eyre::Result<PathBuf> {
-    let home = env::var("HOME").or_else(|_| env::var("USERPROFILE"))?;
-    let cache = PathBuf::from(home).join(".txdecode").join("cache");
-    fs::create_dir_all(&cache)?;
-    Ok(cache)
-}
-
-/// Returns the cache file path for a given contract address.
-fn cache_path(address: &str) -> eyre::Result<PathBuf> {
-    Ok(cache_dir()?.join(format!("{}.json", address.to_lowercase())))
-}
-
-/// Loads the cached ABI for the given contract address, if it exists.
-fn load_cache_abi(address: &str) -> Option<Vec<Function>> {
-    let path = cache_path(address).ok()?;
-    let content = fs::read_to_string(path).ok()?;
-    serde_json::from_str(&content).ok()
-}
-
-/// Saves the given ABI to the cache for the specified contract address.
-fn save_cached_abi(address: &str, abi: &[Function]) -> eyre::Result<()> {
-    let path = cache_path(address)?;
-    let json = serde_json::to_string_pretty(abi)?;
-    fs::write(path, json)?;
-    Ok(())
-}
-
-/// Extracts the first four bytes from the given byte slice to use as a function selector.
-fn selector(data: &Bytes) -> eyre::Result<[u8; 4]> {
-    data.get(..4)
-        .and_then(|s| s.try_into().ok())
-        .ok_or_else(|| eyre!("data too short to extract selector"))
-}
 
-/// Looks up the given function selector on the 4byte.directory API and returns a list of matching
-/// function signatures.
-async fn lookup_selector(selector: [u8; 4]) -> eyre::Result<Vec<String>> {
-    let hex_sig = format!("0x{}", hex::encode(selector));
-    let url = format!(
-        "https://www.4byte.directory/api/v1/signatures/?hex_signature={}",
-        hex_sig
-    );
+    /// Resolve ENS names for decoded addresses (requires network access)
+    #[arg(long)]
+    ens: bool,
 
-    let client = Client::builder().timeout(Duration::from_secs(5)).build()?;
+    /// Decode the full internal call tree via `debug_traceTransaction` (requires a node with
+    /// debug_* support)
+    #[arg(long)]
+    trace: bool,
 
-    let response: FourByteResponse = client.get(&url).send().await?.json().await?;
+    /// Decode the transaction's emitted event logs instead of its calldata
+    #[arg(long)]
+    logs: bool,
 
-    Ok(response
-        .results
-        .into_iter()
-        .map(|r| r.text_signature)
-        .collect())
-}
+    /// Resolve signatures from the offline cache only; never hit 4byte/Etherscan over HTTP
+    #[arg(long)]
+    offline: bool,
 
-/// Parses a function signature string (e.g., "transfer(address,uint256)")
-/// into an Alloy Function that can decode calldata.
-fn parse_signature(sig: &str) -> eyre::Result<Function> {
-    // alloys built-in parser for Solidity signatures
-    Function::parse(sig).map_err(|e| eyre!("failed to parse signature '{}': {}", sig, e))
-}
+    /// Path to a full contract ABI JSON file; calldata is resolved by selector against it
+    /// instead of the 4byte/Etherscan lookup path (requires --input)
+    #[arg(long, value_name = "PATH", requires = "input")]
+    abi: Option<String>,
 
-/// Attempts to decode the given calldata using the provided Alloy Function.
-/// DynSolValue is a dynamic representation of Solidity values (not at compile time).
-fn try_decode(func: &Function, calldata: &Bytes) -> eyre::Result<Vec<DynSolValue>> {
-    // Skip the first 4 bytes (the function selector)
-    let params = calldata
-        .get(4..)
-        .ok_or_else(|| eyre!("calldata missing parameters"))?;
+    /// Treat --input as a deployment transaction's constructor argument tail (no selector) and
+    /// decode it against the constructor declared in --abi
+    #[arg(long, requires = "abi")]
+    constructor: bool,
 
-    // Decode the parameters using the function's input ABI
-    let decoded = func
-        .abi_decode_input(params)
-        .map_err(|e| eyre!("failed to decode: {}", e))?;
-
-    Ok(decoded)
+    /// Emit machine-readable JSON instead of a formatted table
+    #[arg(long)]
+    json: bool,
 }
 
-/// Fetches the ABI from Etherscan for the given contract address and looks for a function
-/// matching the provided selector.
-async fn fetch_etherscan_abi(
-    contract_address: &str,
-    selector: [u8; 4],
-    api_key: &str,
-) -> eyre::Result<Function> {
-    // Check cache first
-    if let Some(cached_abi) = load_cache_abi(contract_address) {
-        if let Some(func) = cached_abi.iter().find(|f| f.selector() == selector) {
-            return Ok(func.clone());
-        }
-    }
-
-    // Fetch from Etherscan
-    let url = format!(
-        "https://api.etherscan.io/v2/api?module=contract&action=getabi&address={}&apikey={}",
-        contract_address, api_key
-    );
-
-    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
-
-    let response: EtherscanResponse = client.get(&url).send().await?.json().await?;
-
-    if response.status != "1" {
-        bail!("failed to fetch ABI from Etherscan: {}", response.result);
+async fn resolve_ens_names_if_enabled(args: &Args, call: &decode::DecodedCall) -> EnsNames {
+    if args.ens {
+        display::resolve_ens_names(call, &args.rpc).await
+    } else {
+        EnsNames::new()
     }
-
-    let abi: Vec<Function> = serde_json::from_str(&response.result)
-        .map_err(|e| eyre!("failed to parse ABI JSON: {}", e))?;
-
-    // Cache the ABI for future use
-    save_cached_abi(contract_address, &abi)?;
-
-    abi.into_iter()
-        .find(|f| f.selector() == selector)
-        .ok_or_else(|| {
-            eyre!(
-                "function with selector 0x{} not found in ABI",
-                hex::encode(selector)
-            )
-        })
 }
 
-/// Decodes the given calldata by looking up possible function signatures and trying to decode
-/// with each until one succeeds.
-async fn decode_calldata(
-    calldata: &Bytes,
-    contract_address: Option<&str>, // Optional
-    etherscan_key: Option<&str>,    // Optional
-) -> eyre::Result<(Function, Vec<DynSolValue>)> {
-    let sel = selector(calldata)?;
-    let signatures = lookup_selector(sel).await?;
-
-    if signatures.is_empty() {
-        bail!("no signatures found for selector 0x{}", hex::encode(sel));
-    }
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    // for better error reporting
+    color_eyre::install()?;
+    let args = Args::parse();
 
-    // Priroritize common signatures (e.g., ERC-20 transfer)
-    let mut prioritized: Vec<&String> = signatures
-        .iter()
-        .filter(|s| WELL_KNOWN_FUNC_NAME.iter().any(|wk| s.starts_with(wk)))
-        .collect();
+    // Decode raw calldata if --input is provided
+    if let Some(input_hex) = &args.input {
+        let calldata = hex::decode(input_hex.trim_start_matches("0x"))?;
+        let bytes = Bytes::from(calldata);
 
-    // Append the rest of the signatures
-    prioritized.extend(
-        signatures
-            .iter()
-            .filter(|s| !WELL_KNOWN_FUNC_NAME.iter().any(|wk| s.starts_with(wk))),
-    );
+        if let Some(abi_path) = &args.abi {
+            let abi_json = std::fs::read_to_string(abi_path)?;
+            let abi: alloy_json_abi::JsonAbi = serde_json::from_str(&abi_json)?;
+
+            if args.constructor {
+                let ctor = abi
+                    .constructor
+                    .as_ref()
+                    .ok_or_else(|| eyre::eyre!("ABI has no constructor entry"))?;
+                let params = decode::decode_constructor(ctor, &bytes)?;
+                if args.json {
+                    let json = display::constructor_to_json(ctor, &params);
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                } else {
+                    display::display_constructor(ctor, &params);
+                }
+                return Ok(());
+            }
 
-    // Try to decode using each signature until one works
-    for sig in prioritized {
-        if let Ok(func) = parse_signature(sig) {
-            if let Ok(decoded) = try_decode(&func, calldata) {
-                return Ok((func, decoded));
+            match decode::decode_with_abi(&abi, &bytes)? {
+                decode::AbiDispatch::Function {
+                    func,
+                    params,
+                    alternatives,
+                } => {
+                    if args.json {
+                        let json = display::decoded_to_json(&func.name, &params, &func);
+                        println!("{}", serde_json::to_string_pretty(&json)?);
+                    } else {
+                        display::display_decoded(&func.name, &params, &func);
+                    }
+                    if !alternatives.is_empty() {
+                        eprintln!(
+                            "ℹ️  {} other selector-colliding function(s) were also tried but didn't decode cleanly.",
+                            alternatives.len()
+                        );
+                    }
+                }
+                decode::AbiDispatch::Error {
+                    error,
+                    params,
+                    alternatives,
+                } => {
+                    if args.json {
+                        let json = display::decoded_error_to_json(&error, &params);
+                        println!("{}", serde_json::to_string_pretty(&json)?);
+                    } else {
+                        display::display_decoded_error(&error, &params);
+                    }
+                    if !alternatives.is_empty() {
+                        eprintln!(
+                            "ℹ️  {} other selector-colliding error(s) were also tried but didn't decode cleanly.",
+                            alternatives.len()
+                        );
+                    }
+                }
             }
+            return Ok(());
         }
-    }
 
-    // Fallback to Etherscan ABI if contract address and API key are provided
-    if let (Some(addr), Some(key)) = (contract_address, etherscan_key) {
-        let func = fetch_etherscan_abi(addr, sel, key).await?;
-        let decoded = try_decode(&func, calldata)?;
-        return Ok((func, decoded));
+        match decode::decode_calldata_tree(
+            &bytes,
+            None,
+            args.etherscan_key.as_deref(),
+            None,
+            decode::DEFAULT_MAX_NESTED_DEPTH,
+            args.offline,
+        )
+        .await
+        {
+            Ok(call) => {
+                if args.json {
+                    let json = display::decoded_call_to_json(&call);
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                } else {
+                    let ens_names = resolve_ens_names_if_enabled(&args, &call).await;
+                    display::display_decoded_tree(&call, &ens_names);
+                }
+            }
+            Err(e) => match decode::decode_revert(&bytes) {
+                Ok((err, params)) => {
+                    if args.json {
+                        let json = display::decoded_error_to_json(&err, &params);
+                        println!("{}", serde_json::to_string_pretty(&json)?);
+                    } else {
+                        display::display_decoded_error(&err, &params);
+                    }
+                }
+                Err(_) => println!("❌ Failed to decode calldata: {}", e),
+            },
+        }
+        return Ok(());
     }
 
-    bail!(
-        "all {} signatures failed to decode calldata",
-        signatures.len()
-    )
-}
+    // Fetch and decode a transaction by hash over the configured RPC endpoint
+    if let Some(tx_hash) = &args.tx_hash {
+        let tx = match rpc::get_transaction_by_hash(&args.rpc, tx_hash).await? {
+            Some(tx) => tx,
+            None => {
+                eprintln!("⚠️  Transaction not found (unknown hash or still pending).");
+                return Ok(());
+            }
+        };
 
-/// Formats a DynSolValue into a human-readable string.
-fn format_value(value: &DynSolValue) -> String {
-    match value {
-        DynSolValue::Address(addr) => {
-            // Format checksum address
-            let add_str = format!("{:?}", addr);
+        if args.json && (args.trace || args.logs) {
+            eprintln!("❌ --json is not yet supported together with --trace/--logs.");
+            return Ok(());
+        }
 
-            // Check for well-known address
-            if addr.is_zero() {
-                format!("{} (Zero Address)", add_str)
-            } else {
-                add_str
+        if args.trace {
+            match trace::trace_transaction(
+                &args.rpc,
+                tx_hash,
+                args.etherscan_key.as_deref(),
+                None,
+                args.offline,
+            )
+            .await?
+            {
+                Some(call) => display::display_trace(&call),
+                None => eprintln!(
+                    "⚠️  Tracing unavailable: the configured RPC node doesn't support debug_traceTransaction."
+                ),
             }
+            return Ok(());
         }
-        DynSolValue::Uint(val, bits) => {
-            // Format bigint with underscores
-            let num_str = val.to_string();
-            if num_str.len() > 6 {
-                // Insert underscores every 3 digits from the right
-                let formatted = num_str
-                    .chars()
-                    .rev()
-                    .collect::<Vec<_>>()
-                    .chunks(3)
-                    .map(|chunk| chunk.iter().collect::<String>())
-                    .collect::<Vec<_>>()
-                    .join("_")
-                    .chars()
-                    .rev()
-                    .collect::<String>();
-                format!("{} (uint{})", formatted, bits)
-            } else {
-                format!("{} (uint{})", num_str, bits)
+
+        if args.logs {
+            let log_entries = logs::fetch_logs(&args.rpc, tx_hash).await?;
+            if log_entries.is_empty() {
+                eprintln!("⚠️  Transaction emitted no logs.");
+                return Ok(());
             }
-        }
-        DynSolValue::Bool(b) => format!("{}", b),
-        DynSolValue::Bytes(bytes) => {
-            if bytes.len() <= 32 {
-                format!("0x{}", hex::encode(bytes))
-            } else {
-                format!("0x{}... ({} bytes)", hex::encode(&bytes[..32]), bytes.len())
+
+            for log in &log_entries {
+                match logs::decode_log(log, args.etherscan_key.as_deref(), None, args.offline).await {
+                    Ok(decoded) => display::display_decoded_log(&decoded),
+                    Err(e) => println!("❌ Failed to decode log from {}: {}", log.address, e),
+                }
             }
+            return Ok(());
         }
-        _ => format!("{:?}", value),
-    }
-}
 
-/// Displays the decoded function name and parameters in a formatted table.
-fn display_decoded(func_name: &str, params: &[DynSolValue], func: &Function) {
-    let mut table = Table::new();
-    table.set_header(vec![
-        Cell::new("Parameter")
-            .fg(Color::Cyan)
-            .add_attribute(Attribute::Bold),
-        Cell::new("Type")
-            .fg(Color::Yellow)
-            .add_attribute(Attribute::Bold),
-        Cell::new("Value")
-            .fg(Color::Green)
-            .add_attribute(Attribute::Bold),
-    ]);
-
-    // Zip parameters with their types from the function ABI
-    for (i, (param, input)) in params.iter().zip(&func.inputs).enumerate() {
-        table.add_row(vec![
-            Cell::new(if input.name.is_empty() {
-                format!("param{}", i)
-            } else {
-                input.name.clone()
-            }),
-            Cell::new(input.ty.to_string()).fg(Color::Yellow),
-            Cell::new(format_value(param)).fg(Color::White),
-        ]);
-    }
-
-    println!("\n✅ Function: {}", func_name);
-    println!("{}", table);
-}
-
-#[tokio::main]
-async fn main() -> eyre::Result<()> {
-    // for better error reporting
-    color_eyre::install()?;
-    let args = Args::parse();
-
-    // Decode raw calldata if --input is provided
-    if let Some(input_hex) = args.input {
-        let calldata = hex::decode(input_hex.trim_start_matches("0x"))?;
-        let bytes = Bytes::from(calldata);
-
-        match decode_calldata(&bytes, None, args.etherscan_key.as_deref()).await {
-            Ok((func, params)) => display_decoded(&func.name, &params, &func),
-            Err(e) => println!("❌ Failed to decode calldata: {}", e),
+        let Some(to) = tx.to else {
+            eprintln!("⚠️  Contract-creation transaction: no callee to decode calldata against.");
+            return Ok(());
+        };
+
+        match decode::decode_calldata_tree(
+            &tx.input,
+            Some(&to),
+            args.etherscan_key.as_deref(),
+            None,
+            decode::DEFAULT_MAX_NESTED_DEPTH,
+            args.offline,
+        )
+        .await
+        {
+            Ok(call) => {
+                if args.json {
+                    let json = display::decoded_call_to_json(&call);
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                } else {
+                    let ens_names = resolve_ens_names_if_enabled(&args, &call).await;
+                    display::display_decoded_tree(&call, &ens_names);
+                }
+            }
+            Err(e) => match decode::decode_revert(&tx.input) {
+                Ok((err, params)) => {
+                    if args.json {
+                        let json = display::decoded_error_to_json(&err, &params);
+                        println!("{}", serde_json::to_string_pretty(&json)?);
+                    } else {
+                        display::display_decoded_error(&err, &params);
+                    }
+                }
+                Err(_) => println!("❌ Failed to decode calldata: {}", e),
+            },
         }
         return Ok(());
     }
 
-    // TODO: Fetch transaction by hash (Step 10)
-    if let Some(_tx_hash) = args.tx_hash {
-        eprintln!("⚠️  Transaction decoding not yet implemented. Use --input for now.");
-        return Ok(());
-    }
-
     eprintln!("❌ Error: Provide either a transaction hash or --input <calldata>");
     Ok(())
 }
@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use alloy::{
+    hex,
+    primitives::{Bytes, B256},
+};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::TxDecodeError;
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    result: Option<Value>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+/// A transaction as returned by `eth_getTransactionByHash`.
+///
+/// Only the fields `txdecode` cares about are modeled; the node response carries
+/// many more (gas, nonce, signature, ...) that we don't need.
+#[derive(Debug, Deserialize)]
+pub struct Transaction {
+    pub input: Bytes,
+    pub to: Option<String>,
+}
+
+/// Sends a JSON-RPC 2.0 request to the given endpoint and returns the `result` field.
+async fn call(rpc_url: &str, method: &str, params: Value) -> Result<Option<Value>, TxDecodeError> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| TxDecodeError::RpcFailed(e.to_string()))?;
+
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+
+    let response: JsonRpcResponse = client
+        .post(rpc_url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| TxDecodeError::RpcFailed(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| TxDecodeError::RpcFailed(e.to_string()))?;
+
+    if let Some(err) = response.error {
+        return Err(TxDecodeError::RpcFailed(format!(
+            "RPC error {}: {}",
+            err.code, err.message
+        )));
+    }
+
+    Ok(response.result)
+}
+
+/// Fetches a transaction by hash via `eth_getTransactionByHash`.
+///
+/// Returns `Ok(None)` when the node doesn't know about the transaction (wrong hash,
+/// or it's still pending and hasn't propagated to this node).
+pub async fn get_transaction_by_hash(
+    rpc_url: &str,
+    tx_hash: &str,
+) -> Result<Option<Transaction>, TxDecodeError> {
+    let result = call(
+        rpc_url,
+        "eth_getTransactionByHash",
+        serde_json::json!([tx_hash]),
+    )
+    .await?;
+
+    match result {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => {
+            let tx = serde_json::from_value(value).map_err(|e| {
+                TxDecodeError::RpcFailed(format!("failed to parse transaction: {}", e))
+            })?;
+            Ok(Some(tx))
+        }
+    }
+}
+
+/// A single entry of a transaction receipt's `logs` array.
+#[derive(Debug, Deserialize)]
+pub struct Log {
+    pub address: String,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+}
+
+/// A transaction receipt as returned by `eth_getTransactionReceipt`.
+///
+/// Only `logs` is modeled; the node response also carries gas used, status, and the
+/// bloom filter, which `txdecode` doesn't need.
+#[derive(Debug, Deserialize)]
+pub struct TransactionReceipt {
+    pub logs: Vec<Log>,
+}
+
+/// Fetches a transaction receipt by hash via `eth_getTransactionReceipt`.
+///
+/// Returns `Ok(None)` when the node doesn't know about the transaction yet (unmined or
+/// unknown hash).
+pub async fn get_transaction_receipt(
+    rpc_url: &str,
+    tx_hash: &str,
+) -> Result<Option<TransactionReceipt>, TxDecodeError> {
+    let result = call(
+        rpc_url,
+        "eth_getTransactionReceipt",
+        serde_json::json!([tx_hash]),
+    )
+    .await?;
+
+    match result {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => {
+            let receipt = serde_json::from_value(value).map_err(|e| {
+                TxDecodeError::RpcFailed(format!("failed to parse transaction receipt: {}", e))
+            })?;
+            Ok(Some(receipt))
+        }
+    }
+}
+
+/// A single frame of a `debug_traceTransaction` call tree captured with the `callTracer`
+/// tracer: the call type (`CALL`/`DELEGATECALL`/`STATICCALL`/`CREATE`/...), the callee, the
+/// calldata, the ETH value transferred, and any sub-calls it made.
+#[derive(Debug, Deserialize)]
+pub struct CallFrame {
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub to: Option<String>,
+    #[serde(default)]
+    pub input: Bytes,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub calls: Vec<CallFrame>,
+}
+
+/// Fetches the internal call tree for `tx_hash` via `debug_traceTransaction` with the
+/// `callTracer` tracer config. Propagates the RPC error as-is (callers can distinguish a node
+/// that doesn't support `debug_*` methods by inspecting the error).
+pub async fn debug_trace_call_tracer(
+    rpc_url: &str,
+    tx_hash: &str,
+) -> Result<Option<CallFrame>, TxDecodeError> {
+    let result = call(
+        rpc_url,
+        "debug_traceTransaction",
+        serde_json::json!([tx_hash, { "tracer": "callTracer" }]),
+    )
+    .await?;
+
+    match result {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => {
+            let frame = serde_json::from_value(value).map_err(|e| {
+                TxDecodeError::RpcFailed(format!("failed to parse call trace: {}", e))
+            })?;
+            Ok(Some(frame))
+        }
+    }
+}
+
+/// Performs an `eth_call` against `to` with the given calldata and returns the raw return
+/// data. Used for read-only contract calls (e.g. ENS resolver lookups) that don't need a
+/// signed transaction.
+pub async fn eth_call(rpc_url: &str, to: &str, data: &Bytes) -> Result<Bytes, TxDecodeError> {
+    let result = call(
+        rpc_url,
+        "eth_call",
+        serde_json::json!([{ "to": to, "data": data }, "latest"]),
+    )
+    .await?
+    .ok_or_else(|| TxDecodeError::RpcFailed("eth_call returned no result".to_string()))?;
+
+    let hex_str: String = serde_json::from_value(result).map_err(|e| {
+        TxDecodeError::RpcFailed(format!("failed to parse eth_call result: {}", e))
+    })?;
+    let bytes = hex::decode(hex_str.trim_start_matches("0x"))
+        .map_err(|e| TxDecodeError::RpcFailed(format!("invalid hex in eth_call result: {}", e)))?;
+    Ok(Bytes::from(bytes))
+}
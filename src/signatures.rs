@@ -6,6 +6,9 @@ use eyre::eyre;
 use reqwest::Client;
 use serde::Deserialize;
 
+use crate::cache;
+use crate::error::TxDecodeError;
+
 // #[derive(Deserialize)] lets serde_json auto-parse the API response
 #[derive(Debug, Deserialize)]
 struct FourByteResponse {
@@ -28,30 +31,65 @@ pub const WELL_KNOWN_FUNC_NAME: [&str; 6] = [
 ];
 
 /// Extracts the first four bytes from the given byte slice to use as a function selector.
-pub fn selector(data: &Bytes) -> eyre::Result<[u8; 4]> {
+pub fn selector(data: &Bytes) -> Result<[u8; 4], TxDecodeError> {
     data.get(..4)
         .and_then(|s| s.try_into().ok())
-        .ok_or_else(|| eyre!("data too short to extract selector"))
+        .ok_or(TxDecodeError::SelectorTooShort)
 }
 
-/// Looks up the given function selector on the 4byte.directory API and returns a list of matching
-/// function signatures.
-pub async fn lookup_selector(selector: [u8; 4]) -> eyre::Result<Vec<String>> {
+/// Looks up the given function selector's signatures, consulting the offline cache at
+/// `~/.txdecode/signatures.json` before hitting 4byte.directory. When `offline` is `true`,
+/// only the cache is consulted: a miss returns [`TxDecodeError::SignatureLookupFailed`]
+/// rather than attempting any HTTP request.
+pub async fn lookup_selector(
+    selector: [u8; 4],
+    offline: bool,
+) -> Result<Vec<String>, TxDecodeError> {
+    if let Some(cached) = cache::load_cached_signatures(selector) {
+        return Ok(cached);
+    }
+
+    if offline {
+        return Err(TxDecodeError::SignatureLookupFailed(format!(
+            "0x{} not in offline cache",
+            hex::encode(selector)
+        )));
+    }
+
     let hex_sig = format!("0x{}", hex::encode(selector));
     let url = format!(
         "https://www.4byte.directory/api/v1/signatures/?hex_signature={}",
         hex_sig
     );
 
-    let client = Client::builder().timeout(Duration::from_secs(5)).build()?;
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| TxDecodeError::SignatureLookupFailed(e.to_string()))?;
 
-    let response: FourByteResponse = client.get(&url).send().await?.json().await?;
+    let response: FourByteResponse = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| TxDecodeError::SignatureLookupFailed(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| TxDecodeError::SignatureLookupFailed(e.to_string()))?;
 
-    Ok(response
+    let signatures: Vec<String> = response
         .results
         .into_iter()
         .map(|r| r.text_signature)
-        .collect())
+        .collect();
+
+    // Best-effort: a cache write failure shouldn't fail the lookup itself. Don't cache an
+    // empty result set (no match, or a transient bad response) — otherwise a single failed
+    // lookup would permanently "brick" the selector with no way to force a re-check.
+    if !signatures.is_empty() {
+        let _ = cache::save_cached_signatures(selector, &signatures);
+    }
+
+    Ok(signatures)
 }
 
 /// Parses a function signature string (e.g., "transfer(address,uint256)")
@@ -76,11 +114,7 @@ mod tests {
     fn test_selector_too_short() {
         let data = Bytes::from(vec![0xa9, 0x05]);
         let result = selector(&data);
-        assert!(result.is_err());
-        assert_eq!(
-            result.unwrap_err().to_string(),
-            "data too short to extract selector"
-        );
+        assert!(matches!(result, Err(TxDecodeError::SelectorTooShort)));
     }
 
     #[test]
@@ -93,8 +127,19 @@ mod tests {
     #[tokio::test]
     async fn test_lookup_selector() {
         let sel = [0xa9, 0x05, 0x9c, 0xbb]; // transfer(address,uint256)
-        let sigs = lookup_selector(sel).await.unwrap();
+        let sigs = lookup_selector(sel, false).await.unwrap();
         assert!(!sigs.is_empty());
         assert!(sigs.iter().any(|s| s.contains("transfer")));
     }
+
+    #[tokio::test]
+    async fn test_lookup_selector_offline_miss() {
+        // An obscure selector unlikely to already be cached from another test run.
+        let sel = [0xff, 0xff, 0xff, 0xfe];
+        let result = lookup_selector(sel, true).await;
+        assert!(matches!(
+            result,
+            Err(TxDecodeError::SignatureLookupFailed(_))
+        ));
+    }
 }
@@ -0,0 +1,108 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use alloy::dyn_abi::DynSolValue;
+use alloy_json_abi::Function;
+
+use crate::decode;
+use crate::error::TxDecodeError;
+use crate::rpc::{self, CallFrame};
+
+/// A single frame of a decoded `debug_traceTransaction` call tree: the raw trace info (call
+/// type, callee, ETH value) plus the calldata decoded the same way a top-level transaction is.
+///
+/// `decoded` is `None` when the frame's calldata couldn't be matched to any known signature
+/// (or is too short to carry a selector), not when something went wrong with tracing itself.
+pub struct TracedCall {
+    pub call_type: String,
+    pub to: Option<String>,
+    pub value: Option<String>,
+    pub decoded: Option<(Function, Vec<DynSolValue>)>,
+    pub children: Vec<TracedCall>,
+}
+
+/// Fetches and decodes the full internal call tree for `tx_hash` via `debug_traceTransaction`.
+///
+/// Returns `Ok(None)` when the node doesn't support `debug_*` methods (or has no trace for the
+/// hash), so callers can report that tracing is unavailable rather than erroring out.
+pub async fn trace_transaction(
+    rpc_url: &str,
+    tx_hash: &str,
+    etherscan_key: Option<&str>,
+    chain: Option<u64>,
+    offline: bool,
+) -> eyre::Result<Option<TracedCall>> {
+    let frame = match rpc::debug_trace_call_tracer(rpc_url, tx_hash).await {
+        Ok(Some(frame)) => frame,
+        Ok(None) => return Ok(None),
+        Err(e) if is_unsupported_method(&e) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Some(decode_frame(frame, etherscan_key, chain, offline).await))
+}
+
+/// A node doesn't support `debug_*` methods typically rejects with JSON-RPC error -32601
+/// ("method not found") or a provider-specific "does not exist/is not available" message.
+fn is_unsupported_method(err: &TxDecodeError) -> bool {
+    let msg = err.to_string().to_lowercase();
+    msg.contains("-32601") || msg.contains("does not exist") || msg.contains("not supported")
+}
+
+/// Recursively decodes a call frame and its children. Boxed because `async fn`s can't
+/// recurse directly into themselves.
+fn decode_frame<'a>(
+    frame: CallFrame,
+    etherscan_key: Option<&'a str>,
+    chain: Option<u64>,
+    offline: bool,
+) -> Pin<Box<dyn Future<Output = TracedCall> + Send + 'a>> {
+    Box::pin(async move {
+        let decoded = if frame.input.len() >= 4 {
+            decode::decode_calldata(
+                &frame.input,
+                frame.to.as_deref(),
+                etherscan_key,
+                chain,
+                offline,
+            )
+            .await
+            .ok()
+        } else {
+            None
+        };
+
+        let mut children = Vec::with_capacity(frame.calls.len());
+        for child in frame.calls {
+            children.push(decode_frame(child, etherscan_key, chain, offline).await);
+        }
+
+        TracedCall {
+            call_type: frame.call_type,
+            to: frame.to,
+            value: frame.value,
+            decoded,
+            children,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_unsupported_method() {
+        let err = TxDecodeError::RpcFailed(
+            "RPC error -32601: the method debug_traceTransaction does not exist/is not available"
+                .to_string(),
+        );
+        assert!(is_unsupported_method(&err));
+    }
+
+    #[test]
+    fn test_is_unsupported_method_false_for_other_errors() {
+        let err = TxDecodeError::RpcFailed("RPC error -32000: execution reverted".to_string());
+        assert!(!is_unsupported_method(&err));
+    }
+}